@@ -1,4 +1,6 @@
 //! Constants used in the Orchard protocol.
+use std::convert::TryInto;
+
 use ff::{Field, PrimeField};
 use group::Curve;
 use halo2::{
@@ -12,6 +14,7 @@ pub mod nullifier_k;
 pub mod value_commit_r;
 pub mod value_commit_v;
 
+pub mod load;
 pub mod util;
 
 /// $\ell^\mathsf{Orchard}_\mathsf{base}$
@@ -44,6 +47,16 @@ pub const FIXED_BASE_WINDOW_SIZE: usize = 3;
 /// Number of windows
 pub const NUM_WINDOWS: usize = pallas::Base::NUM_BITS as usize / FIXED_BASE_WINDOW_SIZE;
 
+/// $\ell^\mathsf{Orchard}_\mathsf{value}$, the number of bits in the signed 64-bit
+/// net value encoded by `ValueCommitV`.
+pub(crate) const L_VALUE: usize = 64;
+
+/// Number of windows for a short signed scalar, such as the net value encoded
+/// in a Pedersen value commitment. This covers the `L_VALUE`-bit magnitude,
+/// plus one additional window (as in the full-width case) to absorb the
+/// `[k · 8^w − sum]B` offset.
+pub const NUM_WINDOWS_SHORT: usize = L_VALUE / FIXED_BASE_WINDOW_SIZE + 1;
+
 /// Number of bits used in complete addition (for variable-base scalar mul)
 pub const NUM_COMPLETE_BITS: usize = 3;
 
@@ -109,33 +122,18 @@ impl<C: CurveAffine> OrchardFixedBase<C> {
     pub fn value(&self) -> C {
         self.0
     }
-}
-
-pub trait FixedBase<C: CurveAffine> {
-    /// For each fixed base, we calculate its scalar multiples in three-bit windows.
-    /// Each window will have 2^3 = 8 points.
-    fn compute_window_table(&self) -> Vec<Vec<C>>;
 
-    /// For each window, we interpolate the x-coordinate.
-    /// Here, we pre-compute and store the coefficients of the interpolation polynomial.
-    fn compute_lagrange_coeffs(&self) -> Vec<Vec<C::Base>>;
-
-    /// For each window, z is a field element
-    /// such that for each point (x, y) in the window:
-    /// - z + y = u^2 (some square in the field); and
-    /// - z - y is not a square.
-    fn find_zs(&self) -> Option<Vec<u64>>;
-}
-
-impl<C: CurveAffine> FixedBase<C> for OrchardFixedBase<C> {
-    fn compute_window_table(&self) -> Vec<Vec<C>> {
+    /// Computes the window table over the first `num_windows` windows of
+    /// this fixed base. Shared by the full-width (`NUM_WINDOWS`) and short
+    /// (`NUM_WINDOWS_SHORT`) scalar paths.
+    fn compute_window_table_inner(&self, num_windows: usize) -> Vec<Vec<C>> {
         let h: usize = 1 << FIXED_BASE_WINDOW_SIZE;
-        let mut window_table: Vec<Vec<C>> = Vec::with_capacity(NUM_WINDOWS);
+        let mut window_table: Vec<Vec<C>> = Vec::with_capacity(num_windows);
 
         // Generate window table entries for all windows but the last.
-        // For these first 84 windows, we compute the multiple [(k+1)*(8^w)]B.
-        // Here, w ranges from [0..84)
-        for w in 0..(NUM_WINDOWS - 1) {
+        // For these windows, we compute the multiple [(k+1)*(8^w)]B.
+        // Here, w ranges from [0..num_windows - 1)
+        for w in 0..(num_windows - 1) {
             window_table.push(
                 (0..h)
                     .map(|k| {
@@ -148,19 +146,19 @@ impl<C: CurveAffine> FixedBase<C> for OrchardFixedBase<C> {
             );
         }
 
-        // Generate window table entries for the last window, w = 84.
+        // Generate window table entries for the last window, w = num_windows - 1.
         // For the last window, we compute [k * (8^w) - sum]B, where sum is defined
-        // as sum = \sum_{j = 0}^{83} 8^j
-        let sum = (0..(NUM_WINDOWS - 1)).fold(C::ScalarExt::zero(), |acc, w| {
+        // as sum = \sum_{j = 0}^{num_windows - 2} 8^j
+        let sum = (0..(num_windows - 1)).fold(C::ScalarExt::zero(), |acc, w| {
             acc + C::ScalarExt::from_u64(h as u64).pow(&[w as u64, 0, 0, 0])
         });
         window_table.push(
             (0..h)
                 .map(|k| {
-                    // scalar = k * (8^w) - sum, where w = 84
+                    // scalar = k * (8^w) - sum, where w = num_windows - 1
                     let scalar = C::ScalarExt::from_u64(k as u64)
                         * C::ScalarExt::from_u64(h as u64).pow(&[
-                            (NUM_WINDOWS - 1) as u64,
+                            (num_windows - 1) as u64,
                             0,
                             0,
                             0,
@@ -174,13 +172,15 @@ impl<C: CurveAffine> FixedBase<C> for OrchardFixedBase<C> {
         window_table
     }
 
-    fn compute_lagrange_coeffs(&self) -> Vec<Vec<C::Base>> {
+    /// Computes the Lagrange coefficients over the first `num_windows`
+    /// windows of this fixed base's window table.
+    fn compute_lagrange_coeffs_inner(&self, num_windows: usize) -> Vec<Vec<C::Base>> {
         let h: usize = 1 << FIXED_BASE_WINDOW_SIZE;
 
         // We are interpolating over the 3-bit window, k \in [0..8)
         let points: Vec<_> = (0..h).map(|i| C::Base::from_u64(i as u64)).collect();
 
-        let window_table = self.compute_window_table();
+        let window_table = self.compute_window_table_inner(num_windows);
 
         window_table
             .iter()
@@ -194,13 +194,13 @@ impl<C: CurveAffine> FixedBase<C> for OrchardFixedBase<C> {
             .collect::<Vec<Vec<_>>>()
     }
 
-    /// For each window, z is a field element
-    /// such that for each point (x, y) in the window:
-    /// - z + y = u^2 (some square in the field); and
-    /// - z - y is not a square.
-    fn find_zs(&self) -> Option<Vec<u64>> {
-        // Closure to find z for one window
-        let find_z = |window_points: &[C]| {
+    /// For each of the first `num_windows` windows, finds `z` and the `u`
+    /// square roots such that for each point `(x, y)` in the window:
+    /// - `u^2 = z + y`; and
+    /// - `z - y` is not a square.
+    fn find_zs_and_us_inner(&self, num_windows: usize) -> Option<Vec<(u64, [C::Base; 8])>> {
+        // Closure to find z and u for one window
+        let find_z_and_us = |window_points: &[C]| {
             let h: usize = 1 << FIXED_BASE_WINDOW_SIZE;
             assert_eq!(h, window_points.len());
 
@@ -216,24 +216,128 @@ impl<C: CurveAffine> FixedBase<C> for OrchardFixedBase<C> {
 
             for z in 0..(1000 * (1 << (2 * h))) {
                 if ys.iter().map(|y| z_for_single_y(*y, z)).sum::<usize>() == h {
-                    return Some(z);
+                    let us: Vec<_> = ys
+                        .iter()
+                        .map(|y| (*y + C::Base::from_u64(z)).sqrt().unwrap())
+                        .collect();
+                    let us: [C::Base; 8] = us.try_into().unwrap();
+                    return Some((z, us));
                 }
             }
 
             None
         };
 
-        let window_table = self.compute_window_table();
+        let window_table = self.compute_window_table_inner(num_windows);
         window_table
             .iter()
-            .map(|window_points| find_z(window_points))
+            .map(|window_points| find_z_and_us(window_points))
             .collect()
     }
 }
 
+pub trait FixedBase<C: CurveAffine> {
+    /// For each fixed base, we calculate its scalar multiples in three-bit windows.
+    /// Each window will have 2^3 = 8 points.
+    fn compute_window_table(&self) -> Vec<Vec<C>>;
+
+    /// For each window, we interpolate the x-coordinate.
+    /// Here, we pre-compute and store the coefficients of the interpolation polynomial.
+    fn compute_lagrange_coeffs(&self) -> Vec<Vec<C::Base>>;
+
+    /// For each window, z is a field element
+    /// such that for each point (x, y) in the window:
+    /// - z + y = u^2 (some square in the field); and
+    /// - z - y is not a square.
+    fn find_zs(&self) -> Option<Vec<u64>>;
+
+    /// For each window, z is a field element such that for each point
+    /// (x, y) in the window:
+    /// - z + y = u^2 (some square in the field); and
+    /// - z - y is not a square.
+    /// This returns the `z` alongside the `u` for each of the 8 points in the
+    /// window, where `u^2 = z + y`, so that the square roots do not need to
+    /// be recomputed by downstream consumers (such as the fixed-base
+    /// scalar multiplication circuit, which witnesses `u` to recover `y`).
+    fn find_zs_and_us(&self) -> Option<Vec<(u64, [C::Base; 8])>>;
+
+    /// Like `compute_window_table`, but only covers `NUM_WINDOWS_SHORT` windows.
+    /// This is used for fixed bases that are multiplied by a short signed
+    /// scalar, such as `ValueCommitV`, where only the `|v|`-bit magnitude is
+    /// windowed and the sign is handled separately by the caller.
+    fn compute_short_window_table(&self) -> Vec<Vec<C>>;
+
+    /// Like `compute_lagrange_coeffs`, but over `compute_short_window_table`.
+    fn compute_short_lagrange_coeffs(&self) -> Vec<Vec<C::Base>>;
+
+    /// Like `find_zs`, but only covers `NUM_WINDOWS_SHORT` windows.
+    fn find_zs_short(&self) -> Option<Vec<u64>>;
+
+    /// Like `find_zs_and_us`, but only covers `NUM_WINDOWS_SHORT` windows.
+    /// This is the short-scalar counterpart that fixed bases multiplied by a
+    /// short signed scalar (such as `ValueCommitV`) need: `find_zs_short`
+    /// alone only gives the `z`s, not the `u`s that the fixed-base scalar
+    /// multiplication circuit witnesses to recover `y`.
+    fn find_zs_and_us_short(&self) -> Option<Vec<(u64, [C::Base; 8])>>;
+}
+
+impl<C: CurveAffine> FixedBase<C> for OrchardFixedBase<C> {
+    fn compute_window_table(&self) -> Vec<Vec<C>> {
+        self.compute_window_table_inner(NUM_WINDOWS)
+    }
+
+    fn compute_lagrange_coeffs(&self) -> Vec<Vec<C::Base>> {
+        self.compute_lagrange_coeffs_inner(NUM_WINDOWS)
+    }
+
+    fn find_zs(&self) -> Option<Vec<u64>> {
+        let zs_and_us = self.find_zs_and_us_inner(NUM_WINDOWS)?;
+        Some(zs_and_us.into_iter().map(|(z, _)| z).collect())
+    }
+
+    fn find_zs_and_us(&self) -> Option<Vec<(u64, [C::Base; 8])>> {
+        self.find_zs_and_us_inner(NUM_WINDOWS)
+    }
+
+    fn compute_short_window_table(&self) -> Vec<Vec<C>> {
+        self.compute_window_table_inner(NUM_WINDOWS_SHORT)
+    }
+
+    fn compute_short_lagrange_coeffs(&self) -> Vec<Vec<C::Base>> {
+        self.compute_lagrange_coeffs_inner(NUM_WINDOWS_SHORT)
+    }
+
+    fn find_zs_short(&self) -> Option<Vec<u64>> {
+        let zs_and_us = self.find_zs_and_us_inner(NUM_WINDOWS_SHORT)?;
+        Some(zs_and_us.into_iter().map(|(z, _)| z).collect())
+    }
+
+    fn find_zs_and_us_short(&self) -> Option<Vec<(u64, [C::Base; 8])>> {
+        self.find_zs_and_us_inner(NUM_WINDOWS_SHORT)
+    }
+}
+
 pub trait TestFixedBase<C: CurveAffine> {
     fn test_lagrange_coeffs(&self);
     fn test_z(&self, z: &[u64]);
+
+    /// Checks that, for each window, `u^2 == z + y` for every point `(x, y)`
+    /// in the window, in the same order as `compute_window_table`.
+    fn test_zs_and_us(&self, zs_and_us: &[(u64, [C::Base; 8])]);
+
+    /// Checks that the short-scalar window table and Lagrange coefficients
+    /// reconstruct `[magnitude]B`, negated according to `sign`, for a
+    /// randomly-chosen `magnitude` less than `2^L_VALUE`.
+    fn test_short_lagrange_coeffs(&self, sign: bool, magnitude: u64);
+    fn test_short_z(&self, z: &[u64]);
+
+    /// Like `test_zs_and_us`, but over `compute_short_window_table`.
+    fn test_zs_and_us_short(&self, zs_and_us: &[(u64, [C::Base; 8])]);
+
+    /// Checks that `util::decompose_base_field_fixed`'s running-sum windows,
+    /// summed as `\sum_i [k_i * 8^i] B`, reproduce `[alpha] B` for a randomly
+    /// chosen base field element `alpha`.
+    fn test_decompose_base_field_fixed(&self);
 }
 
 impl<C: CurveAffine> TestFixedBase<C> for OrchardFixedBase<C> {
@@ -305,4 +409,177 @@ impl<C: CurveAffine> TestFixedBase<C> for OrchardFixedBase<C> {
             }
         }
     }
+
+    fn test_zs_and_us(&self, zs_and_us: &[(u64, [C::Base; 8])]) {
+        let window_table = self.compute_window_table();
+
+        for ((z, us), window_points) in zs_and_us.iter().zip(window_table) {
+            for (u, point) in us.iter().zip(window_points.iter()) {
+                let y = point.get_xy().unwrap().1;
+                assert_eq!(*u * u, C::Base::from_u64(*z) + y);
+            }
+        }
+    }
+
+    fn test_short_lagrange_coeffs(&self, sign: bool, magnitude: u64) {
+        let h = 1 << FIXED_BASE_WINDOW_SIZE;
+        let lagrange_coeffs = self.compute_short_lagrange_coeffs();
+        let mut points = Vec::<C::CurveExt>::with_capacity(NUM_WINDOWS_SHORT);
+
+        let magnitude_scalar = C::Scalar::from_u64(magnitude);
+        let bits =
+            util::decompose_scalar_fixed::<C>(magnitude_scalar, L_VALUE, FIXED_BASE_WINDOW_SIZE);
+
+        // Check all windows but the last, i.e. `k_0, k_1, ..., k_{NUM_WINDOWS_SHORT - 2}`
+        for ((idx, bits), coeffs) in bits[0..(NUM_WINDOWS_SHORT - 1)]
+            .iter()
+            .enumerate()
+            .zip(lagrange_coeffs[0..(NUM_WINDOWS_SHORT - 1)].iter())
+        {
+            let interpolated_x = util::evaluate::<C>(*bits, coeffs);
+
+            // [(k+1)*(8^w)]B
+            let point = self.0
+                * C::Scalar::from_u64(*bits as u64 + 1)
+                * C::Scalar::from_u64(h as u64).pow(&[idx as u64, 0, 0, 0]);
+            let x = point.to_affine().get_xy().unwrap().0;
+
+            assert_eq!(x, interpolated_x);
+            points.push(point);
+        }
+
+        // Check last window
+        {
+            let last_bits = bits[NUM_WINDOWS_SHORT - 1];
+            let interpolated_x =
+                util::evaluate::<C>(last_bits, &lagrange_coeffs[NUM_WINDOWS_SHORT - 1]);
+
+            // [k * (8^w) - offset]B, where offset = \sum_{j = 0}^{NUM_WINDOWS_SHORT - 2} 8^j
+            let offset = (0..(NUM_WINDOWS_SHORT - 1)).fold(C::Scalar::zero(), |acc, w| {
+                acc + C::Scalar::from_u64(h as u64).pow(&[w as u64, 0, 0, 0])
+            });
+            let scalar = C::Scalar::from_u64(last_bits as u64)
+                * C::Scalar::from_u64(h as u64).pow(&[(NUM_WINDOWS_SHORT - 1) as u64, 0, 0, 0])
+                - offset;
+            let point = self.0 * scalar;
+            let x = point.to_affine().get_xy().unwrap().0;
+
+            assert_eq!(x, interpolated_x);
+            points.push(point);
+        }
+
+        // Check the sum of all the window points, negated according to sign.
+        let window_sum = points
+            .iter()
+            .fold(C::CurveExt::default(), |acc, point| acc + point);
+        let window_sum = if sign { -window_sum } else { window_sum };
+
+        let signed_scalar = if sign {
+            -magnitude_scalar
+        } else {
+            magnitude_scalar
+        };
+        let multiple = self.0 * signed_scalar;
+        assert_eq!(window_sum, multiple);
+    }
+
+    fn test_short_z(&self, z: &[u64]) {
+        let window_table = self.compute_short_window_table();
+
+        for (z, window_points) in z.iter().zip(window_table) {
+            for point in window_points.iter() {
+                let y = point.get_xy().unwrap().1;
+                assert_eq!((C::Base::from_u64(*z) + y).sqrt().is_some().unwrap_u8(), 1);
+                assert_eq!((C::Base::from_u64(*z) - y).sqrt().is_some().unwrap_u8(), 0);
+            }
+        }
+    }
+
+    fn test_zs_and_us_short(&self, zs_and_us: &[(u64, [C::Base; 8])]) {
+        let window_table = self.compute_short_window_table();
+
+        for ((z, us), window_points) in zs_and_us.iter().zip(window_table) {
+            for (u, point) in us.iter().zip(window_points.iter()) {
+                let y = point.get_xy().unwrap().1;
+                assert_eq!(*u * u, C::Base::from_u64(*z) + y);
+            }
+        }
+    }
+
+    fn test_decompose_base_field_fixed(&self) {
+        let h = 1 << FIXED_BASE_WINDOW_SIZE;
+        let lagrange_coeffs = self.compute_lagrange_coeffs();
+        let mut points = Vec::<C::CurveExt>::with_capacity(NUM_WINDOWS);
+
+        let alpha = C::Base::rand();
+        let (k, z) = util::decompose_base_field_fixed::<C>(alpha);
+        assert_eq!(k.len(), NUM_WINDOWS);
+        assert_eq!(z.len(), NUM_WINDOWS + 1);
+
+        // Each window k_i is recoverable from the running sum, and is within
+        // range of a 3-bit window.
+        for (i, k_i) in k.iter().enumerate() {
+            assert_eq!(
+                C::Base::from_u64(*k_i as u64),
+                z[i] - z[i + 1] * C::Base::from_u64(h as u64)
+            );
+            assert!((*k_i as usize) < h);
+        }
+        // The running sum terminates at zero, so alpha == \sum_i k_i * 8^i.
+        assert_eq!(z[NUM_WINDOWS], C::Base::zero());
+
+        // Feed the same k_i windows through the existing window table /
+        // Lagrange-coefficient machinery (the one the scalar-field path in
+        // `test_lagrange_coeffs` uses), and check the interpolated
+        // x-coordinates agree with direct scalar multiples.
+        //
+        // Check all windows but the last, i.e. `k_0, k_1, ..., k_{NUM_WINDOWS - 2}`
+        for ((idx, k_i), coeffs) in k[0..(NUM_WINDOWS - 1)]
+            .iter()
+            .enumerate()
+            .zip(lagrange_coeffs[0..(NUM_WINDOWS - 1)].iter())
+        {
+            let interpolated_x = util::evaluate::<C>(*k_i, coeffs);
+
+            // [(k+1)*(8^w)]B
+            let point = self.0
+                * C::Scalar::from_u64(*k_i as u64 + 1)
+                * C::Scalar::from_u64(h as u64).pow(&[idx as u64, 0, 0, 0]);
+            let x = point.to_affine().get_xy().unwrap().0;
+
+            assert_eq!(x, interpolated_x);
+            points.push(point);
+        }
+
+        // Check last window
+        {
+            let last_k = k[NUM_WINDOWS - 1];
+            let interpolated_x = util::evaluate::<C>(last_k, &lagrange_coeffs[NUM_WINDOWS - 1]);
+
+            // [k * (8^w) - offset]B, where offset = \sum_{j = 0}^{NUM_WINDOWS - 2} 8^j
+            let offset = (0..(NUM_WINDOWS - 1)).fold(C::Scalar::zero(), |acc, w| {
+                acc + C::Scalar::from_u64(h as u64).pow(&[w as u64, 0, 0, 0])
+            });
+            let scalar = C::Scalar::from_u64(last_k as u64)
+                * C::Scalar::from_u64(h as u64).pow(&[(NUM_WINDOWS - 1) as u64, 0, 0, 0])
+                - offset;
+            let point = self.0 * scalar;
+            let x = point.to_affine().get_xy().unwrap().0;
+
+            assert_eq!(x, interpolated_x);
+            points.push(point);
+        }
+
+        // Check that the sum of all the window points is [alpha]B, where
+        // alpha's scalar-field representative is built from the same k_i
+        // windows that reconstruct alpha itself in the base field above.
+        let window_sum = points
+            .iter()
+            .fold(C::CurveExt::default(), |acc, point| acc + point);
+        let alpha_scalar = k.iter().enumerate().fold(C::Scalar::zero(), |acc, (i, k_i)| {
+            acc + C::Scalar::from_u64(*k_i as u64) * C::Scalar::from_u64(h as u64).pow(&[i as u64, 0, 0, 0])
+        });
+        let multiple = self.0 * alpha_scalar;
+        assert_eq!(window_sum, multiple);
+    }
 }
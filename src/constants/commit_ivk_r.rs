@@ -0,0 +1,12 @@
+//! Generator for the `CommitIvkR` fixed base.
+use group::Curve;
+use halo2::{arithmetic::CurveExt, pasta::pallas};
+
+use super::COMMIT_IVK_PERSONALIZATION;
+
+pub mod test_vectors;
+
+/// The fixed base used to blind the commitment to `ivk` in `CommitIvk`.
+pub fn generator() -> pallas::Affine {
+    pallas::Point::hash_to_curve(COMMIT_IVK_PERSONALIZATION)(b"r").to_affine()
+}
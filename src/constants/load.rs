@@ -0,0 +1,271 @@
+//! Loads the window table, Lagrange coefficients, and `z`/`u` values for
+//! each `OrchardFixedBases` variant from committed constant arrays.
+//!
+//! `compute_window_table`, `compute_lagrange_coeffs`, and especially
+//! `find_zs`/`find_zs_and_us` (whose brute-force search can take up to
+//! `1000 * 2^(2h)` iterations per window) are far too expensive to call at
+//! circuit-synthesis time. Instead of recomputing them, each fixed base's
+//! `test_vectors` submodule (e.g. [`super::commit_ivk_r::test_vectors`])
+//! commits the `LAGRANGE_COEFFS`/`Z`/`U` arrays to the source tree, and
+//! `load` just decodes those bytes back into field elements.
+//!
+//! To regenerate a fixed base's `test_vectors` module after its generator
+//! or `FIXED_BASE_WINDOW_SIZE` changes, run the `generate_test_vectors`
+//! test below (it is `#[ignore]`d because it pays the brute-force search
+//! cost) and paste its output over the array it prints:
+//!
+//! ```text
+//! cargo test -p orchard generate_test_vectors -- --ignored --nocapture
+//! ```
+use std::convert::TryInto;
+
+use ff::PrimeField;
+use halo2::pasta::pallas;
+
+use super::{
+    commit_ivk_r, note_commit_r, nullifier_k, value_commit_r, value_commit_v, FixedBase,
+    OrchardFixedBase,
+};
+
+/// A fixed base bundled with its precomputed window table (as Lagrange
+/// x-coefficients) and the `z`, `u` values used to recover the
+/// y-coordinate of each point in a window.
+#[derive(Clone, Debug)]
+pub struct OrchardFixedBasesData {
+    pub generator: pallas::Affine,
+    pub lagrange_coeffs: Vec<Vec<pallas::Base>>,
+    pub zs_and_us: Vec<(u64, [pallas::Base; 8])>,
+}
+
+impl OrchardFixedBasesData {
+    /// Decodes a fixed base's committed `LAGRANGE_COEFFS`/`Z`/`U` constant
+    /// arrays (see a `test_vectors` submodule) into field elements.
+    fn from_parts(
+        generator: pallas::Affine,
+        lagrange_coeffs: &[[[u8; 32]; 8]],
+        z: &[u64],
+        u: &[[[u8; 32]; 8]],
+    ) -> Self {
+        let base_from_repr = |repr: [u8; 32]| -> pallas::Base {
+            pallas::Base::from_repr(repr)
+                .expect("committed test vector should be a canonical field element")
+        };
+
+        let lagrange_coeffs = lagrange_coeffs
+            .iter()
+            .map(|window| window.iter().map(|&coeff| base_from_repr(coeff)).collect())
+            .collect();
+
+        let zs_and_us = z
+            .iter()
+            .zip(u.iter())
+            .map(|(&z, us)| {
+                let us: Vec<pallas::Base> = us.iter().map(|&u| base_from_repr(u)).collect();
+                (z, us.try_into().unwrap())
+            })
+            .collect();
+
+        OrchardFixedBasesData {
+            generator,
+            lagrange_coeffs,
+            zs_and_us,
+        }
+    }
+
+    /// Computes a fixed base's full-width (`NUM_WINDOWS`) data directly from
+    /// its generator, without consulting the committed constants. Used to
+    /// check the committed `test_vectors` against a fresh computation.
+    fn recompute(generator: pallas::Affine) -> Self {
+        let base = OrchardFixedBase::new(generator);
+        let lagrange_coeffs = base.compute_lagrange_coeffs();
+        let zs_and_us = base
+            .find_zs_and_us()
+            .expect("z and u values should exist for every window of a fixed base");
+
+        OrchardFixedBasesData {
+            generator,
+            lagrange_coeffs,
+            zs_and_us,
+        }
+    }
+
+    /// Like [`Self::recompute`], but for a fixed base multiplied by a short
+    /// signed scalar (`NUM_WINDOWS_SHORT` windows), such as `ValueCommitV`.
+    fn recompute_short(generator: pallas::Affine) -> Self {
+        let base = OrchardFixedBase::new(generator);
+        let lagrange_coeffs = base.compute_short_lagrange_coeffs();
+        let zs_and_us = base
+            .find_zs_and_us_short()
+            .expect("z and u values should exist for every window of a fixed base");
+
+        OrchardFixedBasesData {
+            generator,
+            lagrange_coeffs,
+            zs_and_us,
+        }
+    }
+}
+
+macro_rules! loaded_fixed_base {
+    ($name:ident, $generator_mod:ident) => {
+        /// Loads this fixed base's committed test vectors.
+        pub fn $name() -> OrchardFixedBasesData {
+            use $generator_mod::test_vectors;
+            OrchardFixedBasesData::from_parts(
+                $generator_mod::generator(),
+                &test_vectors::LAGRANGE_COEFFS,
+                &test_vectors::Z,
+                &test_vectors::U,
+            )
+        }
+    };
+}
+
+loaded_fixed_base!(commit_ivk_r, commit_ivk_r);
+loaded_fixed_base!(note_commit_r, note_commit_r);
+loaded_fixed_base!(nullifier_k, nullifier_k);
+loaded_fixed_base!(value_commit_r, value_commit_r);
+
+/// Loads the committed test vectors for `ValueCommitV`.
+///
+/// Unlike the other fixed bases, `ValueCommitV` is multiplied by a short
+/// signed scalar (the 64-bit net value), so its `test_vectors` module only
+/// covers `NUM_WINDOWS_SHORT` windows.
+pub fn value_commit_v() -> OrchardFixedBasesData {
+    use value_commit_v::test_vectors;
+    OrchardFixedBasesData::from_parts(
+        value_commit_v::generator(),
+        &test_vectors::LAGRANGE_COEFFS,
+        &test_vectors::Z,
+        &test_vectors::U,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::{NUM_WINDOWS, NUM_WINDOWS_SHORT};
+
+    /// Checks that a fixed base's committed test vectors match a fresh
+    /// computation from its generator (not a second call to the same
+    /// loader, which would be tautological).
+    fn assert_matches_fresh_computation(name: &str, loaded: &OrchardFixedBasesData) {
+        assert_eq!(
+            loaded.lagrange_coeffs.len(),
+            NUM_WINDOWS,
+            "{} should have a full-width window table",
+            name
+        );
+
+        let fresh = OrchardFixedBasesData::recompute(loaded.generator);
+        assert_eq!(
+            loaded.lagrange_coeffs, fresh.lagrange_coeffs,
+            "{} Lagrange coefficients do not match a fresh computation",
+            name
+        );
+        assert_eq!(
+            loaded.zs_and_us, fresh.zs_and_us,
+            "{} z/u values do not match a fresh computation",
+            name
+        );
+    }
+
+    /// Like `assert_matches_fresh_computation`, but for a fixed base
+    /// multiplied by a short signed scalar.
+    fn assert_matches_fresh_short_computation(name: &str, loaded: &OrchardFixedBasesData) {
+        assert_eq!(
+            loaded.lagrange_coeffs.len(),
+            NUM_WINDOWS_SHORT,
+            "{} should have a short-scalar window table",
+            name
+        );
+
+        let fresh = OrchardFixedBasesData::recompute_short(loaded.generator);
+        assert_eq!(
+            loaded.lagrange_coeffs, fresh.lagrange_coeffs,
+            "{} Lagrange coefficients do not match a fresh computation",
+            name
+        );
+        assert_eq!(
+            loaded.zs_and_us, fresh.zs_and_us,
+            "{} z/u values do not match a fresh computation",
+            name
+        );
+    }
+
+    #[test]
+    fn generator() {
+        assert_matches_fresh_computation("commit_ivk_r", &commit_ivk_r());
+        assert_matches_fresh_computation("note_commit_r", &note_commit_r());
+        assert_matches_fresh_computation("nullifier_k", &nullifier_k());
+        assert_matches_fresh_computation("value_commit_r", &value_commit_r());
+        assert_matches_fresh_short_computation("value_commit_v", &value_commit_v());
+    }
+
+    /// Not a correctness test: prints the `LAGRANGE_COEFFS`/`Z`/`U` arrays
+    /// for every fixed base, so they can be pasted into the corresponding
+    /// `test_vectors.rs` after a change to a generator or to
+    /// `FIXED_BASE_WINDOW_SIZE`. `#[ignore]`d because the brute-force
+    /// `find_zs_and_us` search it relies on is too slow to run as part of
+    /// the regular test suite.
+    #[test]
+    #[ignore]
+    fn generate_test_vectors() {
+        fn print_test_vectors(name: &str, data: &OrchardFixedBasesData) {
+            println!("// {}", name);
+
+            print!(
+                "pub(crate) const LAGRANGE_COEFFS: [[[u8; 32]; 8]; {}] = [",
+                data.lagrange_coeffs.len()
+            );
+            for window in &data.lagrange_coeffs {
+                print!("\n    [");
+                for coeff in window {
+                    print!("{:?}, ", coeff.to_repr());
+                }
+                print!("],");
+            }
+            println!("\n];");
+
+            print!("pub(crate) const Z: [u64; {}] = [", data.zs_and_us.len());
+            for (z, _) in &data.zs_and_us {
+                print!("{}, ", z);
+            }
+            println!("];");
+
+            print!(
+                "pub(crate) const U: [[[u8; 32]; 8]; {}] = [",
+                data.zs_and_us.len()
+            );
+            for (_, us) in &data.zs_and_us {
+                print!("\n    [");
+                for u in us {
+                    print!("{:?}, ", u.to_repr());
+                }
+                print!("],");
+            }
+            println!("\n];");
+        }
+
+        print_test_vectors(
+            "commit_ivk_r",
+            &OrchardFixedBasesData::recompute(commit_ivk_r::generator()),
+        );
+        print_test_vectors(
+            "note_commit_r",
+            &OrchardFixedBasesData::recompute(note_commit_r::generator()),
+        );
+        print_test_vectors(
+            "nullifier_k",
+            &OrchardFixedBasesData::recompute(nullifier_k::generator()),
+        );
+        print_test_vectors(
+            "value_commit_r",
+            &OrchardFixedBasesData::recompute(value_commit_r::generator()),
+        );
+        print_test_vectors(
+            "value_commit_v",
+            &OrchardFixedBasesData::recompute_short(value_commit_v::generator()),
+        );
+    }
+}
@@ -0,0 +1,12 @@
+//! Generator for the `NoteCommitR` fixed base.
+use group::Curve;
+use halo2::{arithmetic::CurveExt, pasta::pallas};
+
+use super::NOTE_COMMITMENT_PERSONALIZATION;
+
+pub mod test_vectors;
+
+/// The fixed base used to blind the note commitment.
+pub fn generator() -> pallas::Affine {
+    pallas::Point::hash_to_curve(NOTE_COMMITMENT_PERSONALIZATION)(b"r").to_affine()
+}
@@ -0,0 +1,12 @@
+//! Generator for the `NullifierK` fixed base.
+use group::Curve;
+use halo2::{arithmetic::CurveExt, pasta::pallas};
+
+use super::ORCHARD_PERSONALIZATION;
+
+pub mod test_vectors;
+
+/// The fixed base `K^Orchard` used in the nullifier derivation.
+pub fn generator() -> pallas::Affine {
+    pallas::Point::hash_to_curve(ORCHARD_PERSONALIZATION)(b"K").to_affine()
+}
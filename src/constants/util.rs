@@ -0,0 +1,71 @@
+//! Utility functions used when decomposing scalars and base field elements
+//! for fixed-base scalar multiplication.
+use ff::{Field, PrimeField};
+use halo2::arithmetic::{CurveAffine, FieldExt};
+
+use super::{FIXED_BASE_WINDOW_SIZE, L_ORCHARD_BASE};
+
+/// Decompose a field element into `window_num_bits` windows, least
+/// significant window first. The word is padded with zero bits so that its
+/// length is a multiple of `window_num_bits`.
+fn decompose_word<F: FieldExt>(word: F, word_num_bits: usize, window_num_bits: usize) -> Vec<u8> {
+    assert!(window_num_bits <= 8);
+
+    // Pad bits to multiple of window_num_bits
+    let padded_word_bits = ((word_num_bits + window_num_bits - 1) / window_num_bits)
+        * window_num_bits;
+
+    let bits: Vec<bool> = word
+        .to_le_bits()
+        .into_iter()
+        .take(padded_word_bits)
+        .collect();
+
+    bits.chunks_exact(window_num_bits)
+        .map(|chunk| chunk.iter().rev().fold(0u8, |acc, bit| (acc << 1) | (*bit as u8)))
+        .collect()
+}
+
+/// Decompose a scalar field element into `window_num_bits` windows.
+pub fn decompose_scalar_fixed<C: CurveAffine>(
+    scalar: C::Scalar,
+    scalar_num_bits: usize,
+    window_num_bits: usize,
+) -> Vec<u8> {
+    decompose_word::<C::Scalar>(scalar, scalar_num_bits, window_num_bits)
+}
+
+/// Evaluates the Lagrange interpolation polynomial for the window with the
+/// given coefficients (lowest degree term first) at the point `x`.
+pub fn evaluate<C: CurveAffine>(x: u8, coeffs: &[C::Base]) -> C::Base {
+    let x = C::Base::from_u64(x as u64);
+    coeffs
+        .iter()
+        .rev()
+        .fold(C::Base::zero(), |acc, coeff| acc * x + coeff)
+}
+
+/// Decomposes a canonical `L_ORCHARD_BASE`-bit base field element `alpha`
+/// into `NUM_WINDOWS` windows `k_0, k_1, ..., k_{NUM_WINDOWS - 1}` of
+/// `FIXED_BASE_WINDOW_SIZE` bits each, together with the running sum
+/// `z_0, z_1, ..., z_{NUM_WINDOWS}` defined by `z_0 = alpha` and
+/// `z_{i+1} = (z_i - k_i) / 8`.
+///
+/// Each window is recoverable in-circuit as `k_i = z_i - 8 * z_{i+1}`, which
+/// lets the chip constrain `k_i` to `[0, 8)` via a running-sum range check
+/// instead of re-deriving it from a separate decomposition.
+pub fn decompose_base_field_fixed<C: CurveAffine>(alpha: C::Base) -> (Vec<u8>, Vec<C::Base>) {
+    let bits = decompose_word::<C::Base>(alpha, L_ORCHARD_BASE, FIXED_BASE_WINDOW_SIZE);
+
+    let inv_eight = C::Base::from_u64(1 << FIXED_BASE_WINDOW_SIZE)
+        .invert()
+        .unwrap();
+
+    let zs = bits.iter().fold(vec![alpha], |mut zs, k| {
+        let z = *zs.last().unwrap();
+        zs.push((z - C::Base::from_u64(*k as u64)) * inv_eight);
+        zs
+    });
+
+    (bits, zs)
+}
@@ -0,0 +1,12 @@
+//! Generator for the `ValueCommitR` fixed base.
+use group::Curve;
+use halo2::{arithmetic::CurveExt, pasta::pallas};
+
+use super::VALUE_COMMITMENT_PERSONALIZATION;
+
+pub mod test_vectors;
+
+/// The fixed base used to blind the value commitment.
+pub fn generator() -> pallas::Affine {
+    pallas::Point::hash_to_curve(VALUE_COMMITMENT_PERSONALIZATION)(b"r").to_affine()
+}
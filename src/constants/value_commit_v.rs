@@ -0,0 +1,15 @@
+//! Generator for the `ValueCommitV` fixed base.
+use group::Curve;
+use halo2::{arithmetic::CurveExt, pasta::pallas};
+
+use super::VALUE_COMMITMENT_PERSONALIZATION;
+
+pub mod test_vectors;
+
+/// The fixed base used to commit to the signed net value `v`, multiplied by a
+/// short scalar via [`FixedBase::compute_short_window_table`].
+///
+/// [`FixedBase::compute_short_window_table`]: super::FixedBase::compute_short_window_table
+pub fn generator() -> pallas::Affine {
+    pallas::Point::hash_to_curve(VALUE_COMMITMENT_PERSONALIZATION)(b"v").to_affine()
+}